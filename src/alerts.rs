@@ -0,0 +1,176 @@
+//! Incident and status-degradation alerting.
+//!
+//! [`AlertWatcher`] compares successive `Status`/`Incidents` responses and
+//! fires an [`Alert`] through every registered sink when a server leaves
+//! `"operational"`, returns to it, or a new incident appears. Repeated polls
+//! of the same underlying event are deduplicated so a sink only sees each
+//! transition once.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use reqwest::Client;
+use serde::Serialize;
+
+use crate::{Error, IncidentDetails, Incidents, Status};
+
+const OPERATIONAL: &str = "operational";
+
+/// An event worth notifying someone about.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Alert {
+    /// `server` changed to a status other than `"operational"`, either from
+    /// `"operational"` or from another non-operational status.
+    StatusDegraded {
+        server: String,
+        old_status: String,
+        new_status: String,
+    },
+    /// `server` returned to `"operational"` after being degraded.
+    StatusResolved { server: String, old_status: String },
+    /// An entry in `Incidents.incidents` for `server` whose title and status
+    /// pair has not been seen before; a status change on a known incident
+    /// fires this again.
+    IncidentOpened {
+        server: String,
+        incident: IncidentDetails,
+    },
+}
+
+/// Posts each fired `Alert` as a JSON payload to a webhook URL.
+pub struct WebhookSink {
+    url: String,
+    client: Client,
+}
+
+impl WebhookSink {
+    pub fn new(url: impl Into<String>) -> Result<Self, Error> {
+        let client = Client::builder().build().map_err(|e| Error {
+            reason: e.to_string(),
+        })?;
+        Ok(WebhookSink {
+            url: url.into(),
+            client,
+        })
+    }
+
+    async fn notify(&self, alert: &Alert) {
+        let _ = self.client.post(&self.url).json(alert).send().await;
+    }
+}
+
+enum Sink {
+    Webhook(WebhookSink),
+    Callback(Box<dyn Fn(Alert) + Send + Sync>),
+}
+
+/// Tracks per-server status and per-incident state across polls of
+/// `get_status`/`get_incidents`, firing alerts to registered sinks only when
+/// something actually changed.
+#[derive(Default)]
+pub struct AlertWatcher {
+    last_status: std::collections::HashMap<String, String>,
+    seen_incidents: HashSet<(String, String, String)>,
+    sinks: Vec<Sink>,
+}
+
+impl AlertWatcher {
+    pub fn new() -> Self {
+        AlertWatcher::default()
+    }
+
+    /// Register an HTTP webhook sink that receives a JSON-encoded `Alert`.
+    pub fn add_webhook(&mut self, url: impl Into<String>) -> Result<(), Error> {
+        self.sinks.push(Sink::Webhook(WebhookSink::new(url)?));
+        Ok(())
+    }
+
+    /// Register a plain callback sink, e.g. to forward alerts into an
+    /// application's own event bus.
+    pub fn add_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(Alert) + Send + Sync + 'static,
+    {
+        self.sinks.push(Sink::Callback(Box::new(callback)));
+    }
+
+    /// Compare `status`/`incidents` against the previously observed state,
+    /// dispatch any resulting alerts to every sink, and return them.
+    pub async fn observe(&mut self, status: &Status, incidents: &Incidents) -> Vec<Alert> {
+        let mut fired = Vec::new();
+
+        for (server, new_status) in &status.status {
+            let previous = self
+                .last_status
+                .insert(server.clone(), new_status.clone());
+            if let Some(old_status) = previous {
+                if old_status != *new_status {
+                    if new_status == OPERATIONAL {
+                        fired.push(Alert::StatusResolved {
+                            server: server.clone(),
+                            old_status,
+                        });
+                    } else {
+                        fired.push(Alert::StatusDegraded {
+                            server: server.clone(),
+                            old_status,
+                            new_status: new_status.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        for (server, server_incidents) in &incidents.incidents {
+            for incident in server_incidents {
+                let key = (
+                    server.clone(),
+                    incident.v.title.clone(),
+                    incident.v.status.clone(),
+                );
+                if self.seen_incidents.insert(key) {
+                    fired.push(Alert::IncidentOpened {
+                        server: server.clone(),
+                        incident: incident.v.clone(),
+                    });
+                }
+            }
+        }
+
+        for alert in &fired {
+            self.dispatch(alert).await;
+        }
+
+        fired
+    }
+
+    async fn dispatch(&self, alert: &Alert) {
+        for sink in &self.sinks {
+            match sink {
+                Sink::Webhook(webhook) => webhook.notify(alert).await,
+                Sink::Callback(callback) => callback(alert.clone()),
+            }
+        }
+    }
+}
+
+/// Poll `monitoring` for status/incidents on `interval`, feeding each poll
+/// in which both requests succeed through `watcher` until the task is
+/// dropped. A poll where either request fails is skipped.
+pub async fn watch(
+    monitoring: Arc<crate::AlgoliaMonitoring>,
+    servers: Option<Vec<String>>,
+    mut watcher: AlertWatcher,
+    interval: std::time::Duration,
+) {
+    let mut tick = tokio::time::interval(interval);
+    loop {
+        tick.tick().await;
+        let status = monitoring.get_status(servers.clone()).await;
+        let incidents = monitoring.get_incidents(servers.clone()).await;
+        if let (Ok(status), Ok(incidents)) = (status, incidents) {
+            watcher.observe(&status, &incidents).await;
+        }
+    }
+}
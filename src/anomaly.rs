@@ -0,0 +1,217 @@
+//! Online anomaly detection over the `DataPoint` series returned by
+//! `get_latency` and `get_infrastructure_metrics`.
+//!
+//! Two detectors are provided: a robust EWMA detector for series without a
+//! known season, and a seasonal detector that compares each point against a
+//! per-phase baseline (useful for strongly periodic metrics like
+//! `cpu_usage`). [`detect`] picks whichever fits the amount of history and
+//! the `period` the series was fetched with.
+
+use crate::DataPoint;
+
+/// Floor applied to deviation estimates so a constant series never divides
+/// by zero.
+const EPSILON: f64 = 1e-6;
+
+/// Which detector flagged an [`Anomaly`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyKind {
+    /// Flagged by the global EWMA mean/deviation detector.
+    Ewma,
+    /// Flagged by comparison against a same-phase seasonal baseline.
+    Seasonal,
+}
+
+/// A single point flagged as unusual, with the detector's confidence
+/// (deviation divided by the detector's deviation estimate, so `k` is
+/// borderline and higher is more confident).
+#[derive(Debug, Clone, Copy)]
+pub struct Anomaly {
+    pub t: u64,
+    pub v: u32,
+    pub score: f64,
+    pub kind: AnomalyKind,
+}
+
+/// Tuning parameters shared by both detectors.
+#[derive(Debug, Clone, Copy)]
+pub struct DetectorConfig {
+    /// EWMA smoothing factor for both the mean and the deviation estimate.
+    pub alpha: f64,
+    /// Number of deviations away from the baseline required to flag a point.
+    pub k: f64,
+}
+
+impl Default for DetectorConfig {
+    fn default() -> Self {
+        DetectorConfig { alpha: 0.3, k: 3.0 }
+    }
+}
+
+/// Number of points per season for a `period` string, matching the cadence
+/// documented on `Period` (see `Period::point_count`).
+pub fn season_length(period: &str) -> Option<usize> {
+    match period {
+        "minute" => Some(10),
+        "hour" => Some(60),
+        "day" => Some(144),
+        "week" => Some(168),
+        "month" => Some(30),
+        _ => None,
+    }
+}
+
+/// Detect anomalies in `series`, using the seasonal detector once at least
+/// one full season of history (per `period`) is available, and falling back
+/// to the EWMA detector otherwise.
+pub fn detect(series: &[DataPoint], period: &str, config: DetectorConfig) -> Vec<Anomaly> {
+    match season_length(period) {
+        Some(season_len) if series.len() >= season_len => {
+            detect_seasonal(series, season_len, config)
+        }
+        _ => detect_ewma(series, config),
+    }
+}
+
+/// Flag points whose deviation from a running EWMA exceeds `k` times the
+/// EWMA of absolute deviation.
+pub fn detect_ewma(series: &[DataPoint], config: DetectorConfig) -> Vec<Anomaly> {
+    let mut anomalies = Vec::new();
+    let mut iter = series.iter();
+    let Some(first) = iter.next() else {
+        return anomalies;
+    };
+
+    let mut mean = first.v as f64;
+    let mut deviation = EPSILON;
+
+    for point in iter {
+        let value = point.v as f64;
+        // diff uses m_{t-1}, matching the spec's `|v_t - m_{t-1}|`.
+        let diff = (value - mean).abs();
+        // d_t = alpha * |v_t - m_{t-1}| + (1 - alpha) * d_{t-1}, updated
+        // before the threshold test so the comparison is against d_t (not
+        // the stale d_{t-1}), per `|v_t - m_{t-1}| > k * d_t`.
+        deviation = (config.alpha * diff + (1.0 - config.alpha) * deviation).max(EPSILON);
+
+        if diff > config.k * deviation {
+            anomalies.push(Anomaly {
+                t: point.t,
+                v: point.v,
+                score: diff / deviation,
+                kind: AnomalyKind::Ewma,
+            });
+        }
+
+        mean = config.alpha * value + (1.0 - config.alpha) * mean;
+    }
+
+    anomalies
+}
+
+/// Flag points whose deviation from their same-phase bucket's median exceeds
+/// `k` times that bucket's median absolute deviation. `season_len` is the
+/// number of points per season (see [`season_length`]).
+pub fn detect_seasonal(series: &[DataPoint], season_len: usize, config: DetectorConfig) -> Vec<Anomaly> {
+    if season_len == 0 {
+        return detect_ewma(series, config);
+    }
+
+    let mut buckets: Vec<Vec<f64>> = vec![Vec::new(); season_len];
+    let mut anomalies = Vec::new();
+
+    for (i, point) in series.iter().enumerate() {
+        let bucket = i % season_len;
+        let value = point.v as f64;
+
+        // Only compare once a full season of baseline history has been seen.
+        if i >= season_len {
+            let baseline = &buckets[bucket];
+            let median = median(baseline);
+            let mad = mad(baseline, median).max(EPSILON);
+            let diff = (value - median).abs();
+
+            if diff > config.k * mad {
+                anomalies.push(Anomaly {
+                    t: point.t,
+                    v: point.v,
+                    score: diff / mad,
+                    kind: AnomalyKind::Seasonal,
+                });
+            }
+        }
+
+        buckets[bucket].push(value);
+    }
+
+    anomalies
+}
+
+fn median(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+fn mad(values: &[f64], median_value: f64) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let deviations: Vec<f64> = values.iter().map(|v| (v - median_value).abs()).collect();
+    median(&deviations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(t: u64, v: u32) -> DataPoint {
+        // `anomaly::tests` is a descendant of the crate root, so it can
+        // construct `DataPoint`'s otherwise-private fields directly.
+        DataPoint { t, v }
+    }
+
+    #[test]
+    fn ewma_flags_a_spike_in_an_otherwise_flat_series() {
+        let mut series: Vec<DataPoint> = (0..20).map(|i| point(i, 50)).collect();
+        series.push(point(20, 5000));
+
+        let anomalies = detect_ewma(&series, DetectorConfig::default());
+
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].v, 5000);
+        assert_eq!(anomalies[0].kind, AnomalyKind::Ewma);
+    }
+
+    #[test]
+    fn seasonal_ignores_points_before_a_full_season() {
+        let series: Vec<DataPoint> = (0..143).map(|i| point(i, (i % 144) as u32)).collect();
+
+        let anomalies = detect_seasonal(&series, 144, DetectorConfig::default());
+
+        assert!(anomalies.is_empty());
+    }
+
+    #[test]
+    fn seasonal_flags_a_deviation_from_the_same_phase_baseline() {
+        let season_len = 24;
+        let mut series: Vec<DataPoint> = (0..season_len * 3)
+            .map(|i| point(i as u64, 10 + (i % season_len) as u32))
+            .collect();
+        // Same phase as index 2, third season in: should normally be ~12.
+        let spike_index = season_len * 2 + 2;
+        series[spike_index] = point(spike_index as u64, 500);
+
+        let anomalies = detect_seasonal(&series, season_len, DetectorConfig::default());
+
+        assert!(anomalies.iter().any(|a| a.v == 500));
+    }
+}
@@ -0,0 +1,257 @@
+//! Prometheus text-exposition output for Algolia infrastructure metrics.
+//!
+//! [`PrometheusExporter`] accumulates the latest [`Metrics`]/[`Status`] samples
+//! per server, labels them using the [`InventoryItem`] entries returned by
+//! `get_inventory`, and renders them as Prometheus exposition text suitable
+//! for a `/metrics` scrape endpoint.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use crate::{Inventory, InventoryItem, Metrics, Status};
+
+/// Name, help text and current samples for one gauge family.
+struct GaugeFamily {
+    name: &'static str,
+    help: &'static str,
+    samples: HashMap<String, (f64, Option<u64>)>,
+}
+
+impl GaugeFamily {
+    fn new(name: &'static str, help: &'static str) -> Self {
+        GaugeFamily {
+            name,
+            help,
+            samples: HashMap::new(),
+        }
+    }
+}
+
+/// Builds Prometheus text-format exposition output from the latest
+/// `Metrics`/`Status` responses, labeled by `server`, `cluster` and `region`.
+pub struct PrometheusExporter {
+    inventory: HashMap<String, InventoryItem>,
+    latency_ms: GaugeFamily,
+    cpu_idle_ratio: GaugeFamily,
+    ram_search_mb: GaugeFamily,
+    ssd_usage_ratio: GaugeFamily,
+    status_operational: GaugeFamily,
+}
+
+impl PrometheusExporter {
+    /// Create an exporter, labeling future samples using `inventory`.
+    pub fn new(inventory: Inventory) -> Self {
+        let inventory = inventory
+            .inventory
+            .into_iter()
+            .map(|item| (item.name.clone(), item))
+            .collect();
+
+        PrometheusExporter {
+            inventory,
+            latency_ms: GaugeFamily::new("algolia_latency_ms", "Search latency in milliseconds"),
+            cpu_idle_ratio: GaugeFamily::new(
+                "algolia_cpu_idle_ratio",
+                "Proportion of CPU idleness, 0 meaning the CPU isn't idle",
+            ),
+            ram_search_mb: GaugeFamily::new(
+                "algolia_ram_search_mb",
+                "RAM usage for search in megabytes",
+            ),
+            ssd_usage_ratio: GaugeFamily::new(
+                "algolia_ssd_usage_ratio",
+                "Proportion of SSD vs RAM usage",
+            ),
+            status_operational: GaugeFamily::new(
+                "algolia_status_operational",
+                "Whether the server reports an operational status (1) or not (0)",
+            ),
+        }
+    }
+
+    /// Record the latest `metrics` observed for `server`, keeping only the
+    /// most recent `DataPoint` of each series.
+    pub fn observe_metrics(&mut self, server: &str, metrics: &Metrics) {
+        Self::record_latest(&mut self.latency_ms, server, metrics.metrics.latency.as_ref(), 1.0);
+        // cpu_usage/ssd_usage are reported as 0-100 percentages; the `_ratio`
+        // gauges are 0-1, so scale down to match the advertised unit.
+        Self::record_latest(
+            &mut self.cpu_idle_ratio,
+            server,
+            metrics.metrics.cpu_usage.as_ref(),
+            0.01,
+        );
+        Self::record_latest(
+            &mut self.ram_search_mb,
+            server,
+            metrics.metrics.ram_search_usage.as_ref(),
+            1.0,
+        );
+        Self::record_latest(
+            &mut self.ssd_usage_ratio,
+            server,
+            metrics.metrics.ssd_usage.as_ref(),
+            0.01,
+        );
+    }
+
+    /// Record the latest `status` observed for each server it covers.
+    pub fn observe_status(&mut self, status: &Status) {
+        for (server, value) in &status.status {
+            let operational = if value == "operational" { 1.0 } else { 0.0 };
+            self.status_operational
+                .samples
+                .insert(server.clone(), (operational, None));
+        }
+    }
+
+    fn record_latest(
+        family: &mut GaugeFamily,
+        server: &str,
+        series: Option<&HashMap<String, Vec<crate::DataPoint>>>,
+        scale: f64,
+    ) {
+        let Some(series) = series else { return };
+        let Some(points) = series.get(server) else {
+            return;
+        };
+        let Some(latest) = points.last() else { return };
+        family
+            .samples
+            .insert(server.to_owned(), (latest.v as f64 * scale, Some(latest.t)));
+    }
+
+    /// Render all recorded samples as Prometheus text-format exposition.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for family in [
+            &self.latency_ms,
+            &self.cpu_idle_ratio,
+            &self.ram_search_mb,
+            &self.ssd_usage_ratio,
+            &self.status_operational,
+        ] {
+            self.render_family(&mut out, family);
+        }
+        out
+    }
+
+    fn render_family(&self, out: &mut String, family: &GaugeFamily) {
+        if family.samples.is_empty() {
+            return;
+        }
+        let _ = writeln!(out, "# HELP {} {}", family.name, family.help);
+        let _ = writeln!(out, "# TYPE {} gauge", family.name);
+        for (server, (value, timestamp)) in &family.samples {
+            let (cluster, region) = self
+                .inventory
+                .get(server)
+                .map(|item| (item.cluster.as_str(), item.region.as_str()))
+                .unwrap_or(("", ""));
+            let _ = write!(
+                out,
+                "{}{{server=\"{}\",cluster=\"{}\",region=\"{}\"}} {}",
+                family.name, server, cluster, region, value
+            );
+            match timestamp {
+                Some(t) => {
+                    let _ = writeln!(out, " {}", t);
+                }
+                None => {
+                    let _ = writeln!(out);
+                }
+            }
+        }
+    }
+}
+
+/// Runs a `PrometheusExporter` as a scrape endpoint, behind the
+/// `exporter-serve` feature so the crate doesn't pull in networking
+/// dependencies by default.
+#[cfg(feature = "exporter-serve")]
+pub mod serve {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::Mutex;
+
+    use super::PrometheusExporter;
+    use crate::{AlgoliaMonitoring, Error, Metric, Period};
+
+    /// Fetch the inventory from `monitoring` once, poll it for status/metrics
+    /// on `interval`, and serve the rendered exposition text on
+    /// `GET /metrics` at `addr`.
+    pub async fn serve(
+        monitoring: Arc<AlgoliaMonitoring>,
+        servers: Vec<String>,
+        interval: Duration,
+        addr: &str,
+    ) -> Result<(), Error> {
+        let inventory = monitoring.get_inventory().await?;
+        let exporter = Arc::new(Mutex::new(PrometheusExporter::new(inventory)));
+
+        let poller = exporter.clone();
+        let poll_monitoring = monitoring.clone();
+        let poll_servers = servers.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Ok(status) = poll_monitoring.get_status(None).await {
+                    poller.lock().await.observe_status(&status);
+                }
+                // `Metric::All` already covers every server in one response,
+                // so fetch it once per poll rather than once per server.
+                if let Ok(metrics) = poll_monitoring
+                    .get_infrastructure_metrics(Metric::All, Period::Hour)
+                    .await
+                {
+                    let mut poller = poller.lock().await;
+                    for server in &poll_servers {
+                        poller.observe_metrics(server, &metrics);
+                    }
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+
+        let listener = TcpListener::bind(addr).await.map_err(|e| Error {
+            reason: e.to_string(),
+        })?;
+        loop {
+            let (mut socket, _) = listener.accept().await.map_err(|e| Error {
+                reason: e.to_string(),
+            })?;
+            let exporter = exporter.clone();
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                let read = match socket.read(&mut buf).await {
+                    Ok(read) => read,
+                    Err(_) => return,
+                };
+
+                let request_line = String::from_utf8_lossy(&buf[..read]);
+                let mut parts = request_line.lines().next().unwrap_or("").split_whitespace();
+                let method = parts.next().unwrap_or("");
+                let path = parts.next().unwrap_or("");
+
+                let response = if method == "GET" && path == "/metrics" {
+                    let body = exporter.lock().await.render();
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                } else {
+                    let body = "Not Found";
+                    format!(
+                        "HTTP/1.1 404 Not Found\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                };
+                let _ = socket.write_all(response.as_bytes()).await;
+            });
+        }
+    }
+}
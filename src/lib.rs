@@ -1,9 +1,23 @@
 use reqwest::Client;
 use serde::de::DeserializeOwned;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
 
-#[derive(Debug, Deserialize)]
+pub mod alerts;
+pub mod anomaly;
+pub mod exporter;
+pub mod monitor;
+pub mod usage;
+
+/// A single sample of a metric series.
+///
+/// `t` is a Unix epoch timestamp **in milliseconds**, matching the 13-digit
+/// timestamps the Algolia monitoring API returns; `v` is the metric's value
+/// at that instant, in whatever unit the series documents (see
+/// `Metric`).
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
 pub struct DataPoint {
     t: u64,
     v: u32,
@@ -25,7 +39,7 @@ pub struct Incident {
     v: IncidentDetails,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct IncidentDetails {
     title: String,
     body: String,
@@ -73,6 +87,134 @@ pub struct Metrics {
     metrics: MetricsGroup,
 }
 
+/// A metric exposed by `get_infrastructure_metrics`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// Average build time of the indices, in seconds.
+    AvgBuildTime,
+    /// Proportion of SSD vs RAM usage in % (0% means no SSD utilization, 32 GB
+    /// storage used on 64 GB RAM system is 50%).
+    SsdUsage,
+    /// RAM usage for the search in MB.
+    RamSearchUsage,
+    /// RAM usage for the indexing in MB.
+    RamIndexingUsage,
+    /// Proportion of CPU idleness in % (0% means the CPU isn't idle).
+    CpuUsage,
+    /// All of the above.
+    All,
+}
+
+impl Metric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Metric::AvgBuildTime => "avg_build_time",
+            Metric::SsdUsage => "ssd_usage",
+            Metric::RamSearchUsage => "ram_search_usage",
+            Metric::RamIndexingUsage => "ram_indexing_usage",
+            Metric::CpuUsage => "cpu_usage",
+            Metric::All => "*",
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TryFrom<&str> for Metric {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Error> {
+        match value {
+            "avg_build_time" => Ok(Metric::AvgBuildTime),
+            "ssd_usage" => Ok(Metric::SsdUsage),
+            "ram_search_usage" => Ok(Metric::RamSearchUsage),
+            "ram_indexing_usage" => Ok(Metric::RamIndexingUsage),
+            "cpu_usage" => Ok(Metric::CpuUsage),
+            "*" => Ok(Metric::All),
+            other => Err(Error {
+                reason: format!("unknown metric `{}`", other),
+            }),
+        }
+    }
+}
+
+/// The period of time to get a metric over, for `get_infrastructure_metrics`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    /// 1 minute ago, 1 point per 10 seconds (10 points).
+    Minute,
+    /// 1 hour ago, 1 point per 1 minute (60 points).
+    Hour,
+    /// 1 day ago, 1 point per 10 minutes (144 points).
+    Day,
+    /// 1 week ago, 1 point per 1 hour (168 points).
+    Week,
+    /// 1 month ago, 1 point per 1 day (30 points).
+    Month,
+}
+
+impl Period {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Period::Minute => "minute",
+            Period::Hour => "hour",
+            Period::Day => "day",
+            Period::Week => "week",
+            Period::Month => "month",
+        }
+    }
+
+    /// Number of `DataPoint`s a series covering this period is expected to
+    /// have.
+    pub fn point_count(&self) -> usize {
+        match self {
+            Period::Minute => 10,
+            Period::Hour => 60,
+            Period::Day => 144,
+            Period::Week => 168,
+            Period::Month => 30,
+        }
+    }
+
+    /// Expected time between consecutive `DataPoint`s over this period.
+    pub fn step(&self) -> Duration {
+        match self {
+            Period::Minute => Duration::from_secs(10),
+            Period::Hour => Duration::from_secs(60),
+            Period::Day => Duration::from_secs(600),
+            Period::Week => Duration::from_secs(3_600),
+            Period::Month => Duration::from_secs(86_400),
+        }
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TryFrom<&str> for Period {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Error> {
+        match value {
+            "minute" => Ok(Period::Minute),
+            "hour" => Ok(Period::Hour),
+            "day" => Ok(Period::Day),
+            "week" => Ok(Period::Week),
+            "month" => Ok(Period::Month),
+            other => Err(Error {
+                reason: format!("unknown period `{}`", other),
+            }),
+        }
+    }
+}
+
 pub struct AlgoliaMonitoring {
     api_key: Option<String>,
     app_id: Option<String>,
@@ -128,24 +270,24 @@ impl AlgoliaMonitoring {
     }
 
     /// This method gets a metric over a period of time
-    /// `metric` is the metric to get
-    /// - `avg_build_time`: Average build time of the indices in seconds
-    /// - `ssd_usage`: proportion of SSD vs RAM usage in % (0% means no SSD utilization, 32 GB storage used on 64 GB RAM system is 50%)
-    /// - `ram_search_usage`: RAM usage for the search in MB
-    /// - `ram_indexing_usage`: RAM usage for the indexing in MB
-    /// - `cpu_usage`: proportion of CPU idleness in % (0% means the CPU isn’t idle)
-    /// - `*`: All of the above
-    /// `period` is the period of time to get the metric over
-    /// - `minute`: 1 minute ago, 1 point per 10 seconds (10 points)
-    /// - `hour`: 1 hour ago, 1 point per 1 minute (60 points)
-    /// - `day`: 1 day ago, 1 point per 10 minutes (144 points)
-    /// - `week`: 1 week ago, 1 point per 1 hour (168 points)
-    /// - `month`: 1 month ago, 1 point per 1 day (30 points)
-    pub async fn get_infrastructure_metrics(&self, metric: String, period: String) -> Result<Metrics, Error> {
+    pub async fn get_infrastructure_metrics(&self, metric: Metric, period: Period) -> Result<Metrics, Error> {
         let path = format!("infrastructure/{}/period/{}", metric, period);
         self.fetch_data::<Metrics>(path.as_str()).await
     }
 
+    /// Back-compat convenience for callers still passing raw strings.
+    /// Returns an `Error` for unrecognized `metric`/`period` values instead
+    /// of sending a request that's doomed to fail.
+    pub async fn get_infrastructure_metrics_str(
+        &self,
+        metric: &str,
+        period: &str,
+    ) -> Result<Metrics, Error> {
+        let metric = Metric::try_from(metric)?;
+        let period = Period::try_from(period)?;
+        self.get_infrastructure_metrics(metric, period).await
+    }
+
     fn get_http_client(&self) -> Result<Client, reqwest::Error> {
         let mut headers = reqwest::header::HeaderMap::new();
         if !self.api_key.is_some() && !self.app_id.is_some() {
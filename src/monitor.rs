@@ -0,0 +1,276 @@
+//! Continuous polling of the Algolia monitoring API into in-memory
+//! time-series windows, turning the one-shot [`AlgoliaMonitoring`] client
+//! into a long-running agent suitable for dashboards.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+
+use crate::{AlgoliaMonitoring, DataPoint, Metric, Period};
+
+/// A single point observed for `server`/`metric`, as it came off the wire.
+#[derive(Debug, Clone)]
+pub struct ObservedPoint {
+    pub server: String,
+    pub metric: &'static str,
+    pub point: DataPoint,
+}
+
+/// Configuration for a [`Monitor`]'s polling loop.
+#[derive(Debug, Clone)]
+pub struct MonitorConfig {
+    /// Servers to poll latency for (infrastructure metrics cover all servers).
+    pub servers: Vec<String>,
+    /// How often to poll the Algolia API.
+    pub poll_interval: Duration,
+    /// Maximum number of points retained per server/metric ring buffer.
+    pub retention: usize,
+    /// Flush a batch to subscribers once this many points have buffered.
+    pub batch_size: usize,
+    /// Flush a (non-empty) batch once this much time has elapsed since the
+    /// first buffered point, even if `batch_size` hasn't been reached.
+    pub batch_max_delay: Duration,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        MonitorConfig {
+            servers: Vec::new(),
+            poll_interval: Duration::from_secs(60),
+            // Period::Minute reports points on a 10s cadence, so 288 points
+            // hold ~48 minutes of history regardless of `poll_interval`.
+            retention: 288,
+            batch_size: 20,
+            batch_max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+type SeriesKey = (String, &'static str);
+type SeriesMap = Arc<Mutex<HashMap<SeriesKey, VecDeque<DataPoint>>>>;
+
+/// Polls `AlgoliaMonitoring` on a background Tokio task, retaining a rolling
+/// window of `DataPoint`s per server/metric and broadcasting newly observed
+/// points to subscribers in debounced batches.
+pub struct Monitor {
+    series: SeriesMap,
+    tx: broadcast::Sender<Vec<ObservedPoint>>,
+    poll_handle: JoinHandle<()>,
+    batch_handle: JoinHandle<()>,
+}
+
+impl Monitor {
+    /// Spawn the background poller and batcher tasks.
+    pub fn spawn(monitoring: Arc<AlgoliaMonitoring>, config: MonitorConfig) -> Self {
+        let series: SeriesMap = Arc::new(Mutex::new(HashMap::new()));
+        let (point_tx, point_rx) = mpsc::channel(1024);
+        let (broadcast_tx, _) = broadcast::channel(1024);
+
+        let poll_handle = tokio::spawn(Self::poll_loop(
+            monitoring,
+            config.clone(),
+            series.clone(),
+            point_tx,
+        ));
+        let batch_handle = tokio::spawn(Self::batch_loop(
+            point_rx,
+            broadcast_tx.clone(),
+            config.batch_size,
+            config.batch_max_delay,
+        ));
+
+        Monitor {
+            series,
+            tx: broadcast_tx,
+            poll_handle,
+            batch_handle,
+        }
+    }
+
+    /// Subscribe to newly observed points, delivered in debounced batches.
+    pub fn subscribe(&self) -> broadcast::Receiver<Vec<ObservedPoint>> {
+        self.tx.subscribe()
+    }
+
+    /// Return the currently retained series for every polled server/metric.
+    pub fn snapshot(&self) -> HashMap<(String, &'static str), Vec<DataPoint>> {
+        self.series
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(key, points)| (key.clone(), points.iter().cloned().collect()))
+            .collect()
+    }
+
+    async fn poll_loop(
+        monitoring: Arc<AlgoliaMonitoring>,
+        config: MonitorConfig,
+        series: SeriesMap,
+        point_tx: mpsc::Sender<ObservedPoint>,
+    ) {
+        let mut interval = tokio::time::interval(config.poll_interval);
+        loop {
+            interval.tick().await;
+
+            if let Ok(metrics) = monitoring
+                .get_infrastructure_metrics(Metric::All, Period::Minute)
+                .await
+            {
+                let group = &metrics.metrics;
+                Self::ingest(
+                    &series,
+                    &point_tx,
+                    config.retention,
+                    "ssd_usage",
+                    group.ssd_usage.as_ref(),
+                )
+                .await;
+                Self::ingest(
+                    &series,
+                    &point_tx,
+                    config.retention,
+                    "ram_search_usage",
+                    group.ram_search_usage.as_ref(),
+                )
+                .await;
+                Self::ingest(
+                    &series,
+                    &point_tx,
+                    config.retention,
+                    "ram_indexing_usage",
+                    group.ram_indexing_usage.as_ref(),
+                )
+                .await;
+                Self::ingest(
+                    &series,
+                    &point_tx,
+                    config.retention,
+                    "cpu_usage",
+                    group.cpu_usage.as_ref(),
+                )
+                .await;
+                Self::ingest(
+                    &series,
+                    &point_tx,
+                    config.retention,
+                    "avg_build_time",
+                    group.avg_build_time.as_ref(),
+                )
+                .await;
+            }
+
+            if !config.servers.is_empty() {
+                if let Ok(latency) = monitoring.get_latency(config.servers.clone()).await {
+                    Self::ingest(
+                        &series,
+                        &point_tx,
+                        config.retention,
+                        "latency",
+                        latency.metrics.latency.as_ref(),
+                    )
+                    .await;
+                }
+            }
+        }
+    }
+
+    async fn ingest(
+        series: &SeriesMap,
+        point_tx: &mpsc::Sender<ObservedPoint>,
+        retention: usize,
+        metric: &'static str,
+        by_server: Option<&HashMap<String, Vec<DataPoint>>>,
+    ) {
+        let Some(by_server) = by_server else { return };
+        for (server, points) in by_server {
+            // Ingest every point newer than what's already retained, not
+            // just the latest one, so sub-poll-interval resolution (e.g. the
+            // 10s cadence within a `Period::Minute` response) isn't dropped.
+            let new_points: Vec<DataPoint> = {
+                let mut series = series.lock().unwrap();
+                let buffer = series
+                    .entry((server.clone(), metric))
+                    .or_insert_with(VecDeque::new);
+                let last_t = buffer.back().map(|p| p.t);
+                let new_points: Vec<DataPoint> = points
+                    .iter()
+                    .copied()
+                    .filter(|p| Some(p.t) > last_t)
+                    .collect();
+                for point in &new_points {
+                    buffer.push_back(*point);
+                }
+                while buffer.len() > retention {
+                    buffer.pop_front();
+                }
+                new_points
+            };
+            for point in new_points {
+                let _ = point_tx
+                    .send(ObservedPoint {
+                        server: server.clone(),
+                        metric,
+                        point,
+                    })
+                    .await;
+            }
+        }
+    }
+
+    async fn batch_loop(
+        mut point_rx: mpsc::Receiver<ObservedPoint>,
+        broadcast_tx: broadcast::Sender<Vec<ObservedPoint>>,
+        batch_size: usize,
+        max_delay: Duration,
+    ) {
+        // The deadline is set once when the first point lands in an empty
+        // buffer and left alone afterwards, so it tracks "time since first
+        // buffered point" rather than resetting on every arrival.
+        let far_future = || tokio::time::Instant::now() + Duration::from_secs(365 * 24 * 3600);
+
+        let mut buffer = Vec::new();
+        let deadline = tokio::time::sleep_until(far_future());
+        tokio::pin!(deadline);
+
+        loop {
+            tokio::select! {
+                received = point_rx.recv() => {
+                    match received {
+                        Some(point) => {
+                            if buffer.is_empty() {
+                                deadline.as_mut().reset(tokio::time::Instant::now() + max_delay);
+                            }
+                            buffer.push(point);
+                            if buffer.len() >= batch_size {
+                                let _ = broadcast_tx.send(std::mem::take(&mut buffer));
+                                deadline.as_mut().reset(far_future());
+                            }
+                        }
+                        None => {
+                            if !buffer.is_empty() {
+                                let _ = broadcast_tx.send(std::mem::take(&mut buffer));
+                            }
+                            return;
+                        }
+                    }
+                }
+                _ = &mut deadline => {
+                    if !buffer.is_empty() {
+                        let _ = broadcast_tx.send(std::mem::take(&mut buffer));
+                    }
+                    deadline.as_mut().reset(far_future());
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Monitor {
+    fn drop(&mut self) {
+        self.poll_handle.abort();
+        self.batch_handle.abort();
+    }
+}
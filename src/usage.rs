@@ -0,0 +1,236 @@
+//! Usage aggregation and cost-estimate reporting.
+//!
+//! Consumes the resource-usage series returned by
+//! `get_infrastructure_metrics(_, Period::Month)`, joins them against
+//! `get_inventory` to group by cluster/region, and produces a
+//! [`UsageReport`] with per-resource statistics, total CPU-busy time, and an
+//! estimated spend figure from a configurable linear cost model.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::{AlgoliaMonitoring, DataPoint, Error, InventoryItem, Metric, Period};
+
+/// Linear `$ / (unit * hour)` cost model used to turn resource series into an
+/// estimated spend figure, plus whether replica nodes should be excluded from
+/// the report.
+#[derive(Debug, Clone, Copy)]
+pub struct UsageConfig {
+    pub ram_cost_per_gb_hour: f64,
+    pub ssd_cost_per_gb_hour: f64,
+    pub exclude_replicas: bool,
+}
+
+impl Default for UsageConfig {
+    fn default() -> Self {
+        UsageConfig {
+            ram_cost_per_gb_hour: 0.0,
+            ssd_cost_per_gb_hour: 0.0,
+            exclude_replicas: true,
+        }
+    }
+}
+
+/// Mean/peak/p95 of a resource series over the reporting window.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ResourceStats {
+    pub mean: f64,
+    pub peak: f64,
+    pub p95: f64,
+}
+
+impl ResourceStats {
+    fn from_points(points: &[DataPoint]) -> Self {
+        if points.is_empty() {
+            return ResourceStats {
+                mean: 0.0,
+                peak: 0.0,
+                p95: 0.0,
+            };
+        }
+
+        let values: Vec<f64> = points.iter().map(|p| value_of(p)).collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let peak = values.iter().cloned().fold(f64::MIN, f64::max);
+
+        let mut sorted = values;
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let rank = ((sorted.len() as f64) * 0.95).ceil() as usize;
+        let index = rank.saturating_sub(1).min(sorted.len() - 1);
+        let p95 = sorted[index];
+
+        ResourceStats { mean, peak, p95 }
+    }
+}
+
+/// Per-cluster usage and cost for the reporting window.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterUsage {
+    pub cluster: String,
+    pub region: String,
+    pub ssd_usage: ResourceStats,
+    pub ram_search_usage: ResourceStats,
+    pub ram_indexing_usage: ResourceStats,
+    pub cpu_usage: ResourceStats,
+    /// Total time, in hours, the cluster's CPUs spent non-idle: integrates
+    /// `(100 - cpu_idle) / 100` across consecutive `DataPoint` timestamps of
+    /// each node's own series and sums across nodes.
+    pub cpu_busy_hours: f64,
+    /// `ram_cost_per_gb_hour * RAM GB-hours + ssd_cost_per_gb_hour * SSD
+    /// percent-hours`. The SSD term integrates the `ssd_usage` percentage
+    /// as-is; it is not converted to GB.
+    pub estimated_cost: f64,
+}
+
+/// A billing/capacity summary grouping resource usage by cluster/region.
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageReport {
+    pub clusters: Vec<ClusterUsage>,
+}
+
+impl UsageReport {
+    /// Render the report as CSV, one row per cluster.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "cluster,region,ssd_mean,ssd_peak,ssd_p95,ram_search_mean,ram_search_peak,ram_search_p95,\
+             ram_indexing_mean,ram_indexing_peak,ram_indexing_p95,cpu_idle_mean,cpu_idle_peak,cpu_idle_p95,\
+             cpu_busy_hours,estimated_cost\n",
+        );
+        for c in &self.clusters {
+            out.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
+                c.cluster,
+                c.region,
+                c.ssd_usage.mean,
+                c.ssd_usage.peak,
+                c.ssd_usage.p95,
+                c.ram_search_usage.mean,
+                c.ram_search_usage.peak,
+                c.ram_search_usage.p95,
+                c.ram_indexing_usage.mean,
+                c.ram_indexing_usage.peak,
+                c.ram_indexing_usage.p95,
+                c.cpu_usage.mean,
+                c.cpu_usage.peak,
+                c.cpu_usage.p95,
+                c.cpu_busy_hours,
+                c.estimated_cost,
+            ));
+        }
+        out
+    }
+}
+
+/// Fetch a month of resource metrics and inventory, and build a `UsageReport`
+/// grouped by cluster/region.
+pub async fn build_report(
+    monitoring: &AlgoliaMonitoring,
+    config: &UsageConfig,
+) -> Result<UsageReport, Error> {
+    let inventory = monitoring.get_inventory().await?;
+    let metrics = monitoring
+        .get_infrastructure_metrics(Metric::All, Period::Month)
+        .await?;
+    let group = &metrics.metrics;
+
+    let mut by_cluster: HashMap<(String, String), Vec<&InventoryItem>> = HashMap::new();
+    for item in &inventory.inventory {
+        if config.exclude_replicas && item.is_replica {
+            continue;
+        }
+        by_cluster
+            .entry((item.cluster.clone(), item.region.clone()))
+            .or_default()
+            .push(item);
+    }
+
+    let mut clusters: Vec<ClusterUsage> = by_cluster
+        .into_iter()
+        .map(|((cluster, region), items)| {
+            let ssd = node_series(&items, group.ssd_usage.as_ref());
+            let ram_search = node_series(&items, group.ram_search_usage.as_ref());
+            let ram_indexing = node_series(&items, group.ram_indexing_usage.as_ref());
+            let cpu = node_series(&items, group.cpu_usage.as_ref());
+
+            // Each node's series has its own timestamps, so integrate every
+            // node's series independently and sum — integrating the merged,
+            // sorted-by-t series directly would zero out most intervals
+            // whenever nodes report at matching timestamps.
+            let cpu_busy_hours = integrate_per_node(&cpu, |v| (100.0 - v).max(0.0) / 100.0);
+            // RAM series are in MB and are converted to GB. SSD usage is a
+            // percentage of RAM and is integrated as-is, so
+            // `ssd_cost_per_gb_hour` is applied per percent-hour.
+            let ram_unit_hours = integrate_per_node(&ram_search, |v| v / 1024.0)
+                + integrate_per_node(&ram_indexing, |v| v / 1024.0);
+            let ssd_unit_hours = integrate_per_node(&ssd, |v| v);
+            let estimated_cost = ram_unit_hours * config.ram_cost_per_gb_hour
+                + ssd_unit_hours * config.ssd_cost_per_gb_hour;
+
+            ClusterUsage {
+                cluster,
+                region,
+                ssd_usage: ResourceStats::from_points(&flatten(&ssd)),
+                ram_search_usage: ResourceStats::from_points(&flatten(&ram_search)),
+                ram_indexing_usage: ResourceStats::from_points(&flatten(&ram_indexing)),
+                cpu_usage: ResourceStats::from_points(&flatten(&cpu)),
+                cpu_busy_hours,
+                estimated_cost,
+            }
+        })
+        .collect();
+
+    clusters.sort_by(|a, b| (a.cluster.as_str(), a.region.as_str()).cmp(&(b.cluster.as_str(), b.region.as_str())));
+    Ok(UsageReport { clusters })
+}
+
+fn value_of(point: &DataPoint) -> f64 {
+    point.v as f64
+}
+
+/// One time-sorted series per node in `items` that has data in `series`.
+/// Kept per-node (rather than merged) so time integration never mixes
+/// timestamps across nodes.
+fn node_series(
+    items: &[&InventoryItem],
+    series: Option<&HashMap<String, Vec<DataPoint>>>,
+) -> Vec<Vec<DataPoint>> {
+    let Some(series) = series else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .filter_map(|item| series.get(&item.name))
+        .map(|points| {
+            let mut points = points.clone();
+            points.sort_by_key(|p| p.t);
+            points
+        })
+        .collect()
+}
+
+/// Merge per-node series into a single flat, time-sorted `Vec<DataPoint>`,
+/// for statistics (mean/peak/p95) that don't depend on time deltas.
+fn flatten(node_series: &[Vec<DataPoint>]) -> Vec<DataPoint> {
+    let mut points: Vec<DataPoint> = node_series.iter().flatten().copied().collect();
+    points.sort_by_key(|p| p.t);
+    points
+}
+
+/// Integrate `f(point.v)` across consecutive timestamps of each node's own
+/// series, in unit-hours, and sum across nodes.
+fn integrate_per_node(node_series: &[Vec<DataPoint>], f: impl Fn(f64) -> f64) -> f64 {
+    node_series.iter().map(|points| integrate(points, &f)).sum()
+}
+
+/// Integrate `f(point.v)` across consecutive timestamps of a single series,
+/// in unit-hours. `DataPoint.t` is epoch milliseconds (see `DataPoint`).
+fn integrate(points: &[DataPoint], f: impl Fn(f64) -> f64) -> f64 {
+    points
+        .windows(2)
+        .map(|pair| {
+            let hours = pair[1].t.saturating_sub(pair[0].t) as f64 / 3_600_000.0;
+            hours * f(value_of(&pair[0]))
+        })
+        .sum()
+}